@@ -0,0 +1,201 @@
+//! A fast, non-cryptographic [`Hasher`] for internal caches keyed on arbitrary byte blobs.
+//!
+//! Mirrors the approach used by the `ahash` crate: two 128-bit lanes, each seeded from a fixed
+//! PI-derived constant, absorb the input one 16-byte block at a time (alternating lanes), each
+//! block folded in via a single AES encryption round. When AES-NI isn't available at runtime,
+//! block folding falls back to a scalar multiply-xor-rotate mix over 64-bit chunks, à la the
+//! `fallback_hash` path. Unlike [`crate::LHashHasher`], this hasher is safe to use with
+//! variable-length keys, since it actually mixes its input rather than taking the first word.
+
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// Lane seeds derived from the hex digits of pi, as used by `ahash`'s own fixed keys.
+const PI_LANE_0: [u8; 16] = [
+    0x24, 0x3f, 0x6a, 0x88, 0x85, 0xa3, 0x08, 0xd3, 0x13, 0x19, 0x8a, 0x2e, 0x03, 0x70, 0x73, 0x44,
+];
+const PI_LANE_1: [u8; 16] = [
+    0xa4, 0x09, 0x38, 0x22, 0x29, 0x9f, 0x31, 0xd0, 0x08, 0x2e, 0xfa, 0x98, 0xec, 0x4e, 0x6c, 0x89,
+];
+
+/// Multiplier for the scalar fallback mix, the odd 64-bit truncation of the golden ratio used by
+/// `fallback_hash`-style multiply-xor-rotate hashers.
+const SCALAR_MULTIPLY: u64 = 0x9e3779b97f4a7c15;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod aes_ni {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    /// Folds `block` into `lane` with a single AES encryption round, AES-NI's canonical
+    /// one-round mixing primitive.
+    ///
+    /// # Safety
+    /// Caller must ensure the `aes` and `sse2` target features are available at runtime.
+    #[target_feature(enable = "aes,sse2")]
+    #[inline]
+    pub(super) unsafe fn fold_block(lane: [u8; 16], block: [u8; 16]) -> [u8; 16] {
+        unsafe {
+            let lane_v = _mm_loadu_si128(lane.as_ptr() as *const __m128i);
+            let block_v = _mm_loadu_si128(block.as_ptr() as *const __m128i);
+            let mixed = _mm_aesenc_si128(_mm_xor_si128(lane_v, block_v), block_v);
+            let mut out = [0u8; 16];
+            _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, mixed);
+            out
+        }
+    }
+}
+
+/// Scalar multiply-xor-rotate fallback, used when AES-NI is unavailable at runtime.
+#[inline]
+fn fold_block_scalar(lane: [u8; 16], block: [u8; 16]) -> [u8; 16] {
+    let mix_half = |lane_half: [u8; 8], block_half: [u8; 8]| -> [u8; 8] {
+        let a = u64::from_le_bytes(lane_half);
+        let b = u64::from_le_bytes(block_half);
+        let h = (a ^ b).wrapping_mul(SCALAR_MULTIPLY);
+        (h ^ h.rotate_left(32)).to_le_bytes()
+    };
+    let mut out = [0u8; 16];
+    out[..8].copy_from_slice(&mix_half(lane[..8].try_into().unwrap(), block[..8].try_into().unwrap()));
+    out[8..].copy_from_slice(&mix_half(lane[8..].try_into().unwrap(), block[8..].try_into().unwrap()));
+    out
+}
+
+/// Folds `block` into `lane`, dispatching to the AES-NI path when the running CPU supports it
+/// and falling back to the scalar mix otherwise.
+#[inline]
+fn fold_block(lane: [u8; 16], block: [u8; 16]) -> [u8; 16] {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::is_x86_feature_detected!("aes") && std::is_x86_feature_detected!("sse2") {
+            // SAFETY: both features were just confirmed present on this CPU.
+            return unsafe { aes_ni::fold_block(lane, block) };
+        }
+    }
+    fold_block_scalar(lane, block)
+}
+
+/// A non-cryptographic [`Hasher`] for arbitrary byte blobs, AES-NI-accelerated when available.
+///
+/// See the module docs for the algorithm. Not suitable for anything security-critical (keyed
+/// hashing, untrusted-input DoS resistance); it exists purely to make non-security-critical
+/// `HashMap`/`HashSet` lookups in the miner cheaper than SipHash.
+#[derive(Clone)]
+pub struct FastHasher {
+    lane0: [u8; 16],
+    lane1: [u8; 16],
+    parity: bool,
+    buffer: [u8; 16],
+    buffer_len: usize,
+    total_len: u64,
+}
+
+impl Default for FastHasher {
+    #[inline]
+    fn default() -> Self {
+        Self { lane0: PI_LANE_0, lane1: PI_LANE_1, parity: false, buffer: [0; 16], buffer_len: 0, total_len: 0 }
+    }
+}
+
+impl FastHasher {
+    #[inline]
+    fn absorb_block(&mut self, block: [u8; 16]) {
+        if self.parity {
+            self.lane1 = fold_block(self.lane1, block);
+        } else {
+            self.lane0 = fold_block(self.lane0, block);
+        }
+        self.parity = !self.parity;
+    }
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total_len += bytes.len() as u64;
+
+        if self.buffer_len > 0 {
+            let needed = 16 - self.buffer_len;
+            let take = needed.min(bytes.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&bytes[..take]);
+            self.buffer_len += take;
+            bytes = &bytes[take..];
+            if self.buffer_len < 16 {
+                return;
+            }
+            self.absorb_block(self.buffer);
+            self.buffer_len = 0;
+        }
+
+        while bytes.len() >= 16 {
+            let block: [u8; 16] = bytes[..16].try_into().unwrap();
+            self.absorb_block(block);
+            bytes = &bytes[16..];
+        }
+
+        self.buffer[..bytes.len()].copy_from_slice(bytes);
+        self.buffer_len = bytes.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut lane0 = self.lane0;
+        let mut lane1 = self.lane1;
+
+        if self.buffer_len > 0 {
+            let mut padded = [0u8; 16];
+            padded[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+            lane0 = fold_block(lane0, padded);
+        }
+
+        let mut len_block = [0u8; 16];
+        len_block[..8].copy_from_slice(&self.total_len.to_le_bytes());
+        lane1 = fold_block(lane1, len_block);
+
+        let combined = fold_block(lane0, lane1);
+        u64::from_le_bytes(combined[..8].try_into().unwrap())
+    }
+}
+
+/// A [`BuildHasherDefault`] for [`FastHasher`], usable anywhere the miner builds a `HashMap`/
+/// `HashSet` over non-security-critical keys.
+pub type FastBuildHasher = BuildHasherDefault<FastHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = FastHasher::default();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_deterministic_and_sensitive_to_input() {
+        assert_eq!(hash_of(&"hello world"), hash_of(&"hello world"));
+        assert_ne!(hash_of(&"hello world"), hash_of(&"hello worlt"));
+        assert_ne!(hash_of(&""), hash_of(&"\0"));
+        assert_ne!(hash_of(&[0u8; 16]), hash_of(&[0u8; 17]));
+    }
+
+    #[test]
+    fn test_long_input_crosses_multiple_blocks() {
+        let short = vec![0x42u8; 15];
+        let long = vec![0x42u8; 4096];
+        assert_ne!(hash_of(&short), hash_of(&long));
+    }
+
+    #[test]
+    fn test_hashmap_round_trip() {
+        let mut map: HashMap<Vec<u8>, u32, FastBuildHasher> = HashMap::default();
+        for i in 0..2000u32 {
+            map.insert(i.to_le_bytes().to_vec(), i);
+        }
+        assert_eq!(map.len(), 2000);
+        for i in 0..2000u32 {
+            assert_eq!(map.get(i.to_le_bytes().as_slice()), Some(&i));
+        }
+    }
+}
@@ -2,18 +2,34 @@
 
 use std::{
     array::TryFromSliceError,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Display, Formatter},
-    hash::{Hash as StdHash, Hasher as StdHasher},
-    str::{self, FromStr},
+    hash::{BuildHasherDefault, Hash as StdHash, Hasher as StdHasher},
+    ops::BitXor,
+    str::FromStr,
 };
 
 
+#[cfg(feature = "fast-hash")]
+mod fast_hash;
+#[cfg(feature = "fast-hash")]
+pub use fast_hash::{FastBuildHasher, FastHasher};
+
 pub const HASH_SIZE: usize = 32;
 
 
+/// `#[repr(transparent)]` is load-bearing: it's what makes the `bytemuck::Pod`/`Zeroable` impls
+/// below sound, since it guarantees `LHash` has the exact same layout as `[u8; HASH_SIZE]`.
 #[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
 pub struct LHash(pub(crate) [u8; HASH_SIZE]);
 
+// SAFETY: `LHash` is `#[repr(transparent)]` over `[u8; HASH_SIZE]`, which is itself `Pod`: no
+// padding, no interior mutability, and every bit pattern is valid.
+unsafe impl bytemuck::Zeroable for LHash {}
+unsafe impl bytemuck::Pod for LHash {}
+
 
 
 impl From<[u8; HASH_SIZE]> for LHash {
@@ -76,6 +92,50 @@ impl LHash {
     pub fn from_u64_word(word: u64) -> Self {
         Self::from_le_u64([0, 0, 0, word])
     }
+
+    /// Returns `true` iff this hash, read as a big-endian 256-bit unsigned integer, is `<= target`.
+    ///
+    /// This is the core mining-loop check: a block hash "meets" a difficulty target when it is
+    /// numerically no greater than the target.
+    #[inline(always)]
+    pub fn meets_target(&self, target: &LHash) -> bool {
+        self <= target
+    }
+}
+
+/// `Ord` treats `self.0` as a big-endian 256-bit unsigned integer (most-significant byte first),
+/// i.e. plain lexicographic comparison of the raw bytes. This is the opposite of the
+/// little-endian word view used by [`LHash::iter_le_u64`] and the `StdHash` impl below, and is
+/// deliberately inconsistent with it: `Ord` exists for numeric/target comparisons (see
+/// [`LHash::meets_target`]), while the word view exists for fast hashing. `Ord` is consistent
+/// with `Eq` and the `PartialEq` impl below, since both compare on raw byte equality.
+impl Eq for LHash {}
+
+impl PartialOrd for LHash {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LHash {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl BitXor for LHash {
+    type Output = LHash;
+
+    /// XORs the raw bytes of both hashes together, e.g. to compute a Kademlia-style distance
+    /// metric between two hashes for peer/work bucketing.
+    #[inline(always)]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        let mut out = [0u8; HASH_SIZE];
+        out.iter_mut().zip(self.0.iter().zip(rhs.0.iter())).for_each(|(out, (a, b))| *out = a ^ b);
+        LHash(out)
+    }
 }
 
 // Override the default Hash implementation, to: A. improve perf a bit (siphash works over u64s), B. allow a hasher to just take the first u64.
@@ -96,13 +156,85 @@ impl PartialEq for LHash {
     }
 }
 
+/// An identity [`StdHasher`] for [`LHash`] keys, exploiting the fact that `LHash`'s bytes are
+/// already the output of a cryptographic hash and thus uniformly distributed: there's no need
+/// to run them through SipHash again.
+///
+/// [`LHash::hash`] above writes its four little-endian words in order via `StdHasher::write_u64`,
+/// so this hasher records only the first such word and ignores everything written after it;
+/// `finish` simply returns that word. This yields the first 8 bytes of the hash as the map key
+/// with zero mixing, at the cost of being meaningless for any other `StdHash` impl.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LHashHasher {
+    word: u64,
+    written: bool,
+}
+
+impl StdHasher for LHashHasher {
+    #[inline(always)]
+    fn write(&mut self, _bytes: &[u8]) {
+        unreachable!("LHashHasher only supports hashing LHash, which writes u64 words")
+    }
+
+    #[inline(always)]
+    fn write_u64(&mut self, word: u64) {
+        if !self.written {
+            self.word = word;
+            self.written = true;
+        }
+    }
+
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.word
+    }
+}
+
+/// A [`BuildHasherDefault`] for [`LHashHasher`], usable as the `S` parameter of a `HashMap`/`HashSet`.
+pub type BuildLHashHasher = BuildHasherDefault<LHashHasher>;
+
+/// A `HashMap` keyed on [`LHash`] that skips SipHash in favor of [`LHashHasher`].
+pub type LHashMap<V> = HashMap<LHash, V, BuildLHashHasher>;
+
+/// A `HashSet` of [`LHash`] that skips SipHash in favor of [`LHashHasher`].
+pub type LHashSet = HashSet<LHash, BuildLHashHasher>;
+
+
+/// Encodes `bytes` into a stack-allocated hex buffer, truncates to `f.precision()` hex nibbles
+/// (full length if unset), and feeds the result through `f.pad_integral()` so width/fill/alignment
+/// are honored like any other formatted value. `{:#}` prefixes `0x`.
+#[inline]
+fn fmt_hex_exact(bytes: &[u8; HASH_SIZE], upper: bool, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let mut hex = [0u8; HASH_SIZE * 2];
+    let encoded =
+        if upper { faster_hex::hex_encode_upper(bytes, &mut hex) } else { faster_hex::hex_encode(bytes, &mut hex) }
+            .expect("The output is exactly twice the size of the input");
+    let len = f.precision().map_or(encoded.len(), |precision| precision.min(encoded.len()));
+    let encoded = &encoded[..len];
+    // `pad_integral` only writes the `0x` prefix when `{:#}` is set, includes it in the width
+    // calculation, and (unlike `f.pad`) does not re-truncate `encoded` by precision, since we've
+    // already truncated it ourselves above.
+    f.pad_integral(true, "0x", encoded)
+}
 
 impl Display for LHash {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut hex = [0u8; HASH_SIZE * 2];
-        faster_hex::hex_encode(&self.0, &mut hex).expect("The output is exactly twice the size of the input");
-        f.write_str(unsafe { str::from_utf8_unchecked(&hex) })
+        fmt_hex_exact(&self.0, false, f)
+    }
+}
+
+impl std::fmt::LowerHex for LHash {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_hex_exact(&self.0, false, f)
+    }
+}
+
+impl std::fmt::UpperHex for LHash {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_hex_exact(&self.0, true, f)
     }
 }
 pub trait ToHex {
@@ -156,6 +288,122 @@ impl FromHex for LHash {
     }
 }
 
+/// The longest a base58-encoded `LHash` can be: `ceil(HASH_SIZE * log(256) / log(58))`.
+pub const MAX_BASE58_LEN: usize = 44;
+
+/// A decode error for [`LHash::from_base58`]/[`FromBase58::from_base58`].
+#[derive(Debug)]
+pub enum Base58Error {
+    /// The input is not valid base58 (e.g. contains a character outside the Bitcoin alphabet).
+    Decode(bs58::decode::Error),
+    /// The input decoded successfully but to a byte string of the wrong length for a `LHash`.
+    InvalidLength(usize),
+}
+
+impl Display for Base58Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Base58Error::Decode(err) => write!(f, "invalid base58 string: {err}"),
+            Base58Error::InvalidLength(len) => write!(f, "base58 string decodes to {len} bytes, expected {HASH_SIZE}"),
+        }
+    }
+}
+
+impl LHash {
+    /// Encodes the raw bytes (big-endian, i.e. as stored) using the standard Bitcoin base58
+    /// alphabet.
+    pub fn to_base58(&self) -> String {
+        bs58::encode(&self.0).into_string()
+    }
+
+    /// Decodes a base58 string, rejecting anything that doesn't decode to exactly `HASH_SIZE`
+    /// bytes (leading `1` characters, which encode leading zero bytes, are accounted for by the
+    /// underlying decoder before this check runs).
+    pub fn from_base58(base58_str: &str) -> Result<Self, Base58Error> {
+        let bytes = bs58::decode(base58_str).into_vec().map_err(Base58Error::Decode)?;
+        if bytes.len() != HASH_SIZE {
+            return Err(Base58Error::InvalidLength(bytes.len()));
+        }
+        Ok(LHash(<[u8; HASH_SIZE]>::try_from(bytes.as_slice()).unwrap()))
+    }
+}
+
+pub trait ToBase58 {
+    fn to_base58(&self) -> String;
+}
+
+impl ToBase58 for LHash {
+    fn to_base58(&self) -> String {
+        LHash::to_base58(self)
+    }
+}
+
+pub trait FromBase58: Sized {
+    type Error: std::fmt::Display;
+    fn from_base58(base58_str: &str) -> Result<Self, Self::Error>;
+}
+
+impl FromBase58 for LHash {
+    type Error = Base58Error;
+    fn from_base58(base58_str: &str) -> Result<Self, Self::Error> {
+        LHash::from_base58(base58_str)
+    }
+}
+
+/// Human-readable formats (JSON, TOML, ...) get lowercase hex via [`Display`]/[`FromStr`];
+/// binary formats (bincode, ...) get the raw 32 bytes.
+impl serde::Serialize for LHash {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+struct LHashVisitor;
+
+impl serde::de::Visitor<'_> for LHashVisitor {
+    type Value = LHash;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a {HASH_SIZE}-byte hash, as a lowercase hex string or raw bytes")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, hex_str: &str) -> Result<Self::Value, E> {
+        LHash::from_str(hex_str).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        LHash::try_from_slice(bytes).map_err(|_| serde::de::Error::invalid_length(bytes.len(), &self))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for LHash {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(LHashVisitor)
+        } else {
+            deserializer.deserialize_bytes(LHashVisitor)
+        }
+    }
+}
+
+impl borsh::BorshSerialize for LHash {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+impl borsh::BorshDeserialize for LHash {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut bytes = [0u8; HASH_SIZE];
+        reader.read_exact(&mut bytes)?;
+        Ok(LHash(bytes))
+    }
+}
+
 pub const ZERO_HASH: LHash = LHash([0; HASH_SIZE]);
 
 pub const EMPTY_MUHASH: LHash = LHash::from_bytes([
@@ -165,8 +413,12 @@ pub const EMPTY_MUHASH: LHash = LHash::from_bytes([
 
 #[cfg(test)]
 mod tests {
-    use super::LHash;
-    use std::str::FromStr;
+    use super::{BuildLHashHasher, LHash, LHashMap, EMPTY_MUHASH, ZERO_HASH};
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        hash::{BuildHasher, Hash, Hasher},
+        str::FromStr,
+    };
 
     #[test]
     fn test_hash_basics() {
@@ -185,4 +437,132 @@ mod tests {
         assert!(matches!(dbg!(LHash::from_str(odd_str)), Err(faster_hex::Error::InvalidLength(len)) if len == 64));
         assert!(matches!(dbg!(LHash::from_str(short_str)), Err(faster_hex::Error::InvalidLength(len)) if len == 64));
     }
+
+    #[test]
+    fn test_ord_is_big_endian() {
+        let smaller = LHash::from_str("0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let bigger = LHash::from_str("0100000000000000000000000000000000000000000000000000000000000000").unwrap();
+        assert!(smaller < bigger);
+        assert!(smaller.meets_target(&bigger));
+        assert!(!bigger.meets_target(&smaller));
+        assert!(smaller.meets_target(&smaller));
+    }
+
+    #[test]
+    fn test_bitxor() {
+        let a = LHash::from_u64_word(0xff);
+        let b = LHash::from_u64_word(0x0f);
+        let x = a ^ b;
+        assert_eq!(x, LHash::from_u64_word(0xf0));
+        assert_eq!(a ^ a, super::ZERO_HASH);
+    }
+
+    fn pseudo_random_hash(seed: u64) -> LHash {
+        let mut bytes = [0u8; 32];
+        for (chunk_idx, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+            let mut hasher = DefaultHasher::new();
+            (seed, chunk_idx).hash(&mut hasher);
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+        LHash::from_bytes(bytes)
+    }
+
+    #[test]
+    fn test_lhash_map_no_collisions_and_round_trip() {
+        let mut map = LHashMap::default();
+        for i in 0..5000u64 {
+            map.insert(pseudo_random_hash(i), i);
+        }
+        assert_eq!(map.len(), 5000);
+        let hasher_outputs: HashSet<u64> = (0..5000u64).map(|i| BuildLHashHasher::default().hash_one(pseudo_random_hash(i))).collect();
+        assert_eq!(hasher_outputs.len(), 5000);
+        for i in 0..5000u64 {
+            assert_eq!(map.get(&pseudo_random_hash(i)), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_serde_json_round_trip() {
+        for hash in [ZERO_HASH, EMPTY_MUHASH, LHash::from_u64_word(0x1234_5678)] {
+            let json = serde_json::to_string(&hash).unwrap();
+            assert_eq!(json, format!("\"{hash}\""));
+            let decoded: LHash = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded, hash);
+        }
+    }
+
+    #[test]
+    fn test_serde_bincode_round_trip() {
+        for hash in [ZERO_HASH, EMPTY_MUHASH, LHash::from_u64_word(0x1234_5678)] {
+            let bytes = bincode::serialize(&hash).unwrap();
+            let decoded: LHash = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(decoded, hash);
+        }
+    }
+
+    #[test]
+    fn test_borsh_round_trip() {
+        for hash in [ZERO_HASH, EMPTY_MUHASH, LHash::from_u64_word(0x1234_5678)] {
+            let bytes = borsh::to_vec(&hash).unwrap();
+            assert_eq!(bytes, hash.as_bytes());
+            let decoded: LHash = borsh::from_slice(&bytes).unwrap();
+            assert_eq!(decoded, hash);
+        }
+    }
+
+    #[test]
+    fn test_base58_round_trip() {
+        for hash in [ZERO_HASH, LHash::from_bytes([0xff; 32])] {
+            let base58 = hash.to_base58();
+            assert!(base58.len() <= super::MAX_BASE58_LEN);
+            let decoded = LHash::from_base58(&base58).unwrap();
+            assert_eq!(decoded, hash);
+        }
+    }
+
+    #[test]
+    fn test_base58_rejects_wrong_length() {
+        use super::FromBase58;
+        // A single extra byte of entropy base58-encodes to a string that decodes to 33 bytes.
+        let too_long = bs58::encode(&[0u8; 33]).into_string();
+        assert!(matches!(LHash::from_base58(&too_long), Err(super::Base58Error::InvalidLength(33))));
+        assert!(<LHash as FromBase58>::from_base58(&too_long).is_err());
+    }
+
+    #[test]
+    fn test_hex_precision_truncation() {
+        let hash_str = "8e40af02265360d59f4ecf9ae9ebf8f00a3118408f5a9cdcbcc9c0f93642f3af";
+        let hash = LHash::from_str(hash_str).unwrap();
+        assert_eq!(format!("{hash:.12}"), hash_str[..12]);
+        assert_eq!(format!("{hash:.0}"), "");
+        assert_eq!(format!("{hash}"), hash_str);
+        // precision beyond the full length is clamped, not padded with garbage
+        assert_eq!(format!("{hash:.1000}"), hash_str);
+    }
+
+    #[test]
+    fn test_hex_alternate_and_case() {
+        let hash_str = "8e40af02265360d59f4ecf9ae9ebf8f00a3118408f5a9cdcbcc9c0f93642f3af";
+        let hash = LHash::from_str(hash_str).unwrap();
+        assert_eq!(format!("{hash:#.8}"), format!("0x{}", &hash_str[..8]));
+        assert_eq!(format!("{hash:X}"), hash_str.to_uppercase());
+        assert_eq!(format!("{hash:x}"), hash_str);
+    }
+
+    #[test]
+    fn test_hex_width_and_alignment() {
+        let hash = LHash::from_u64_word(0);
+        let truncated = format!("{hash:0>10.4}");
+        assert_eq!(truncated, "0000000000");
+        let padded = format!("{hash:->6.2}");
+        assert_eq!(padded, "----00");
+    }
+
+    #[test]
+    fn test_bytemuck_round_trip() {
+        let hashes = [ZERO_HASH, EMPTY_MUHASH, LHash::from_u64_word(0x1234_5678)];
+        let bytes: &[u8] = bytemuck::cast_slice(&hashes);
+        let roundtripped: &[LHash] = bytemuck::cast_slice(bytes);
+        assert_eq!(roundtripped, hashes);
+    }
 }